@@ -0,0 +1,52 @@
+//! Optional [`bindgen`](https://docs.rs/bindgen) integration to generate
+//! Rust FFI bindings from the C header cgo emits alongside a
+//! `c-archive`/`c-shared` build.
+
+use std::path::Path;
+
+use crate::{Error, ErrorKind};
+
+/// Allowlist/blocklist patterns passed through to the underlying
+/// [`bindgen::Builder`].
+#[derive(Clone, Debug, Default)]
+pub(crate) struct BindgenConfig {
+    pub(crate) allowlist: Vec<String>,
+    pub(crate) blocklist: Vec<String>,
+}
+
+/// Runs `bindgen` over the cgo-generated header at `header`, writing the
+/// resulting bindings to `out_file`.
+pub(crate) fn generate(header: &Path, out_file: &Path, config: &BindgenConfig) -> Result<(), Error> {
+    let mut builder = bindgen::Builder::default()
+        .header(header.to_string_lossy().into_owned())
+        .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()));
+
+    for pattern in &config.allowlist {
+        builder = builder.allowlist_item(pattern);
+    }
+    for pattern in &config.blocklist {
+        builder = builder.blocklist_item(pattern);
+    }
+
+    let bindings = builder.generate().map_err(|err| {
+        Error::new(
+            ErrorKind::ToolExecError,
+            &format!(
+                "failed to generate bindings for {}: {}",
+                header.display(),
+                err
+            ),
+        )
+    })?;
+
+    bindings.write_to_file(out_file).map_err(|err| {
+        Error::new(
+            ErrorKind::ToolExecError,
+            &format!(
+                "failed to write bindings to {}: {}",
+                out_file.display(),
+                err
+            ),
+        )
+    })
+}
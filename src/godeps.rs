@@ -0,0 +1,316 @@
+//! Discovery of the Go source files a package depends on, so that
+//! `cargo:rerun-if-changed` can be emitted for them.
+
+use std::{ffi::OsStr, path::Path, path::PathBuf, process};
+
+use crate::{Error, ErrorKind};
+
+/// Runs `go list -json -deps` for the given packages and emits
+/// `cargo:rerun-if-changed` for every Go/cgo/C source file reported,
+/// restricted to files under `root` (the crate's manifest directory) so
+/// that stdlib and module-cache sources outside the workspace are skipped.
+pub(crate) fn emit_rerun_if_changed(
+    go_binary: &Path,
+    envs: &[(&str, &OsStr)],
+    change_dir: Option<&Path>,
+    packages: &[PathBuf],
+    root: &Path,
+) -> Result<(), Error> {
+    let mut cmd = process::Command::new(go_binary);
+    for (key, val) in envs {
+        cmd.env(key, val);
+    }
+    if let Some(change_dir) = change_dir {
+        // This flag is required to be the first flag used in the command as
+        // of Go v1.21: https://tip.golang.org/doc/go1.21#go-command
+        cmd.arg("-C").arg(change_dir);
+    }
+    cmd.args(["list", "-json", "-deps"]);
+    for package in packages {
+        cmd.arg(package);
+    }
+
+    let list_output = cmd.output().map_err(|err| {
+        Error::new(
+            ErrorKind::ToolExecError,
+            &format!("failed to execute go list command: {}", err),
+        )
+    })?;
+    if !list_output.status.success() {
+        return Err(Error::new(
+            ErrorKind::ToolExecError,
+            &format!(
+                "failed to list Go package dependencies ({}): {}",
+                list_output.status,
+                String::from_utf8_lossy(&list_output.stderr).trim()
+            ),
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&list_output.stdout);
+    for package in parse_json_stream(&stdout)? {
+        let Some(dir) = package.get("Dir").and_then(Json::as_str) else {
+            continue;
+        };
+        let dir = PathBuf::from(dir);
+        for key in ["GoFiles", "CgoFiles", "CFiles", "HFiles"] {
+            let Some(files) = package.get(key).and_then(Json::as_array) else {
+                continue;
+            };
+            for file in files {
+                let Some(file) = file.as_str() else {
+                    continue;
+                };
+                let path = dir.join(file);
+                if path.starts_with(root) {
+                    println!("cargo:rerun-if-changed={}", path.display());
+                }
+            }
+        }
+    }
+
+    for key in ["GOOS", "GOARCH", "CC", "CGO_ENABLED", "GOFLAGS"] {
+        println!("cargo:rerun-if-env-changed={}", key);
+    }
+
+    Ok(())
+}
+
+/// A minimal JSON value, sufficient to decode the subset of `go list -json`
+/// output this module cares about.
+enum Json {
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+    Other,
+}
+
+impl Json {
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// `go list -json` prints a sequence of JSON objects concatenated together
+/// rather than wrapping them in an array, so this decodes them one at a
+/// time until the input is exhausted.
+fn parse_json_stream(input: &str) -> Result<Vec<Json>, Error> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut pos = 0;
+    let mut values = Vec::new();
+    loop {
+        skip_whitespace(&chars, &mut pos);
+        if pos >= chars.len() {
+            break;
+        }
+        values.push(parse_value(&chars, &mut pos)?);
+    }
+    Ok(values)
+}
+
+fn parse_value(chars: &[char], pos: &mut usize) -> Result<Json, Error> {
+    skip_whitespace(chars, pos);
+    match chars.get(*pos) {
+        Some('{') => parse_object(chars, pos),
+        Some('[') => parse_array(chars, pos),
+        Some('"') => Ok(Json::String(parse_string(chars, pos)?)),
+        Some(_) => {
+            skip_scalar(chars, pos);
+            Ok(Json::Other)
+        }
+        None => Err(invalid_json("unexpected end of input")),
+    }
+}
+
+fn parse_object(chars: &[char], pos: &mut usize) -> Result<Json, Error> {
+    *pos += 1; // consume '{'
+    let mut fields = Vec::new();
+    loop {
+        skip_whitespace(chars, pos);
+        match chars.get(*pos) {
+            Some('}') => {
+                *pos += 1;
+                break;
+            }
+            Some(',') => {
+                *pos += 1;
+            }
+            Some('"') => {
+                let key = parse_string(chars, pos)?;
+                skip_whitespace(chars, pos);
+                if chars.get(*pos) != Some(&':') {
+                    return Err(invalid_json("expected ':' after object key"));
+                }
+                *pos += 1;
+                let value = parse_value(chars, pos)?;
+                fields.push((key, value));
+            }
+            _ => return Err(invalid_json("malformed object")),
+        }
+    }
+    Ok(Json::Object(fields))
+}
+
+fn parse_array(chars: &[char], pos: &mut usize) -> Result<Json, Error> {
+    *pos += 1; // consume '['
+    let mut items = Vec::new();
+    loop {
+        skip_whitespace(chars, pos);
+        match chars.get(*pos) {
+            Some(']') => {
+                *pos += 1;
+                break;
+            }
+            Some(',') => {
+                *pos += 1;
+            }
+            Some(_) => items.push(parse_value(chars, pos)?),
+            None => return Err(invalid_json("malformed array")),
+        }
+    }
+    Ok(Json::Array(items))
+}
+
+fn parse_string(chars: &[char], pos: &mut usize) -> Result<String, Error> {
+    *pos += 1; // consume opening '"'
+    let mut out = String::new();
+    loop {
+        match chars.get(*pos) {
+            Some('"') => {
+                *pos += 1;
+                return Ok(out);
+            }
+            Some('\\') => {
+                *pos += 1;
+                match chars.get(*pos) {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some(&c @ ('"' | '\\' | '/')) => out.push(c),
+                    Some('u') => {
+                        let hex: String = chars
+                            .get(*pos + 1..*pos + 5)
+                            .ok_or_else(|| invalid_json("truncated unicode escape"))?
+                            .iter()
+                            .collect();
+                        let code = u32::from_str_radix(&hex, 16)
+                            .map_err(|_| invalid_json("invalid unicode escape"))?;
+                        if let Some(c) = char::from_u32(code) {
+                            out.push(c);
+                        }
+                        *pos += 4;
+                    }
+                    _ => return Err(invalid_json("invalid escape sequence")),
+                }
+                *pos += 1;
+            }
+            Some(&c) => {
+                out.push(c);
+                *pos += 1;
+            }
+            None => return Err(invalid_json("unterminated string")),
+        }
+    }
+}
+
+/// Skips a bare token (number, `true`, `false`, or `null`) that isn't a
+/// string, object, or array.
+fn skip_scalar(chars: &[char], pos: &mut usize) {
+    while matches!(chars.get(*pos), Some(c) if !matches!(c, ',' | '}' | ']' | ' ' | '\t' | '\n' | '\r'))
+    {
+        *pos += 1;
+    }
+}
+
+fn skip_whitespace(chars: &[char], pos: &mut usize) {
+    while matches!(chars.get(*pos), Some(' ' | '\t' | '\n' | '\r')) {
+        *pos += 1;
+    }
+}
+
+fn invalid_json(message: &str) -> Error {
+    Error::new(
+        ErrorKind::ToolExecError,
+        &format!("invalid go list output: {}", message),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_single_object() {
+        let values = parse_json_stream(r#"{"Dir": "/tmp/pkg", "GoFiles": ["a.go", "b.go"]}"#)
+            .expect("valid json");
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0].get("Dir").and_then(Json::as_str), Some("/tmp/pkg"));
+        let go_files = values[0].get("GoFiles").and_then(Json::as_array).unwrap();
+        assert_eq!(go_files.len(), 2);
+        assert_eq!(go_files[0].as_str(), Some("a.go"));
+        assert_eq!(go_files[1].as_str(), Some("b.go"));
+    }
+
+    #[test]
+    fn parses_a_concatenated_stream_of_objects() {
+        let values = parse_json_stream(r#"{"ImportPath": "a"}{"ImportPath": "b"}"#)
+            .expect("valid json");
+        assert_eq!(values.len(), 2);
+        assert_eq!(
+            values[0].get("ImportPath").and_then(Json::as_str),
+            Some("a")
+        );
+        assert_eq!(
+            values[1].get("ImportPath").and_then(Json::as_str),
+            Some("b")
+        );
+    }
+
+    #[test]
+    fn ignores_non_object_and_nested_scalar_fields() {
+        let values = parse_json_stream(
+            r#"{"Standard": true, "Deps": ["fmt"], "Incomplete": null, "Name": "main"}"#,
+        )
+        .expect("valid json");
+        assert_eq!(values[0].get("Name").and_then(Json::as_str), Some("main"));
+        assert!(values[0].get("Standard").and_then(Json::as_str).is_none());
+    }
+
+    #[test]
+    fn decodes_string_escapes() {
+        let values =
+            parse_json_stream(r#"{"Dir": "C:\\Go\\src\tmod\u0041"}"#).expect("valid json");
+        assert_eq!(
+            values[0].get("Dir").and_then(Json::as_str),
+            Some("C:\\Go\\src\tmodA")
+        );
+    }
+
+    #[test]
+    fn missing_key_returns_none() {
+        let values = parse_json_stream(r#"{"Dir": "/tmp/pkg"}"#).expect("valid json");
+        assert!(values[0].get("CgoFiles").is_none());
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(parse_json_stream(r#"{"Dir": "#).is_err());
+    }
+}
@@ -25,6 +25,10 @@
 #![forbid(unsafe_code)]
 #![allow(clippy::needless_doctest_main)]
 
+#[cfg(feature = "bindgen")]
+mod bindings;
+mod godeps;
+
 use std::{
     env,
     ffi::{OsStr, OsString},
@@ -36,12 +40,30 @@ use std::{
 /// A builder for the compilation of a Go library.
 #[derive(Clone, Debug)]
 pub struct Build {
+    asmflags: Option<OsString>,
+    #[cfg(feature = "bindgen")]
+    bindgen_allowlist: Vec<String>,
+    #[cfg(feature = "bindgen")]
+    bindgen_blocklist: Vec<String>,
+    #[cfg(feature = "bindgen")]
+    bindgen_enabled: bool,
     build_mode: BuildMode,
     cargo_metadata: bool,
     change_dir: Option<PathBuf>,
+    envs: Vec<(OsString, OsString)>,
+    flags: Vec<OsString>,
+    gcflags: Option<OsString>,
+    go386: Option<Go386>,
+    goamd64: Option<GoAmd64>,
+    goarm: Option<GoArm>,
+    go_binary: Option<PathBuf>,
+    gomips: Option<GoMips>,
+    gomips64: Option<GoMips>,
+    goppc64: Option<GoPpc64>,
     ldflags: Option<OsString>,
     out_dir: Option<PathBuf>,
     packages: Vec<PathBuf>,
+    tags: Vec<String>,
     trimpath: bool,
 }
 
@@ -55,16 +77,76 @@ impl Build {
     /// Returns a new instance of `Build` with the default configuration.
     pub fn new() -> Self {
         Build {
+            asmflags: None,
+            #[cfg(feature = "bindgen")]
+            bindgen_allowlist: Vec::default(),
+            #[cfg(feature = "bindgen")]
+            bindgen_blocklist: Vec::default(),
+            #[cfg(feature = "bindgen")]
+            bindgen_enabled: false,
             build_mode: BuildMode::default(),
             cargo_metadata: true,
             change_dir: None,
+            envs: Vec::default(),
+            flags: Vec::default(),
+            gcflags: None,
+            go386: None,
+            goamd64: None,
+            goarm: None,
+            go_binary: None,
+            gomips: None,
+            gomips64: None,
+            goppc64: None,
             ldflags: None,
             out_dir: None,
             packages: Vec::default(),
+            tags: Vec::default(),
             trimpath: false,
         }
     }
 
+    /// Instruct the builder to pass in the provided asmflags during
+    /// compilation.
+    pub fn asmflags<P: AsRef<OsStr>>(&mut self, asmflags: P) -> &mut Self {
+        self.asmflags = Some(asmflags.as_ref().to_os_string());
+        self
+    }
+
+    /// Instruct the builder to generate Rust FFI bindings from the C header
+    /// cgo writes alongside a `c-archive`/`c-shared` build, using
+    /// [`bindgen`](https://docs.rs/bindgen). The bindings are written to
+    /// `<output>.rs` in the output directory (see [`Build::out_dir`];
+    /// `OUT_DIR` by default), suitable for `include!`-ing.
+    ///
+    /// Requires the `bindgen` feature.
+    #[cfg(feature = "bindgen")]
+    pub fn generate_bindings(&mut self, generate_bindings: bool) -> &mut Self {
+        self.bindgen_enabled = generate_bindings;
+        self
+    }
+
+    /// Adds an allowlist pattern passed through to the underlying
+    /// [`bindgen::Builder`]. Has no effect unless
+    /// [`Build::generate_bindings`] is also enabled.
+    ///
+    /// Requires the `bindgen` feature.
+    #[cfg(feature = "bindgen")]
+    pub fn bindgen_allowlist_item(&mut self, pattern: &str) -> &mut Self {
+        self.bindgen_allowlist.push(pattern.to_owned());
+        self
+    }
+
+    /// Adds a blocklist pattern passed through to the underlying
+    /// [`bindgen::Builder`]. Has no effect unless
+    /// [`Build::generate_bindings`] is also enabled.
+    ///
+    /// Requires the `bindgen` feature.
+    #[cfg(feature = "bindgen")]
+    pub fn bindgen_blocklist_item(&mut self, pattern: &str) -> &mut Self {
+        self.bindgen_blocklist.push(pattern.to_owned());
+        self
+    }
+
     /// Instruct the builder to use the provided build mode.
     ///
     /// For more information, see https://pkg.go.dev/cmd/go#hdr-Build_modes
@@ -90,6 +172,114 @@ impl Build {
         self
     }
 
+    /// Instruct the builder to set the given environment variable in the
+    /// `go build` command's environment, e.g. `GOFLAGS`, `GOPROXY`, or
+    /// `GOEXPERIMENT`.
+    pub fn env<K, V>(&mut self, key: K, val: V) -> &mut Self
+    where
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.envs
+            .push((key.as_ref().to_os_string(), val.as_ref().to_os_string()));
+        self
+    }
+
+    /// Instruct the builder to append the provided argument to the `go
+    /// build` invocation.
+    pub fn flag<P: AsRef<OsStr>>(&mut self, flag: P) -> &mut Self {
+        self.flags.push(flag.as_ref().to_os_string());
+        self
+    }
+
+    /// Instruct the builder to append the provided arguments to the `go
+    /// build` invocation.
+    pub fn flags<I, P>(&mut self, flags: I) -> &mut Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<OsStr>,
+    {
+        for flag in flags {
+            self.flag(flag);
+        }
+        self
+    }
+
+    /// Instruct the builder to pass in the provided gcflags during
+    /// compilation.
+    pub fn gcflags<P: AsRef<OsStr>>(&mut self, gcflags: P) -> &mut Self {
+        self.gcflags = Some(gcflags.as_ref().to_os_string());
+        self
+    }
+
+    /// Instruct the builder to target the given `GO386` variant, overriding
+    /// the value that would otherwise be derived from the target triple.
+    ///
+    /// Only relevant when targeting a 32-bit x86 architecture.
+    pub fn go386(&mut self, go386: Go386) -> &mut Self {
+        self.go386 = Some(go386);
+        self
+    }
+
+    /// Instruct the builder to target the given `GOAMD64` variant, overriding
+    /// the value that would otherwise be derived from the target features.
+    ///
+    /// Only relevant when targeting the `x86_64` architecture.
+    pub fn goamd64(&mut self, goamd64: GoAmd64) -> &mut Self {
+        self.goamd64 = Some(goamd64);
+        self
+    }
+
+    /// Instruct the builder to target the given `GOARM` variant, overriding
+    /// the value that would otherwise be derived from the target triple and
+    /// features.
+    ///
+    /// Only relevant when targeting a 32-bit ARM architecture.
+    pub fn goarm(&mut self, goarm: GoArm) -> &mut Self {
+        self.goarm = Some(goarm);
+        self
+    }
+
+    /// Instruct the builder to invoke the Go toolchain at `path` instead of
+    /// discovering it automatically.
+    ///
+    /// When unset, the `GOC` environment variable is honored first, followed
+    /// by a well-known install location (`/usr/local/go/bin/go`), falling
+    /// back to `go` on `PATH`.
+    pub fn go_binary<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.go_binary = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Instruct the builder to target the given `GOMIPS` variant, overriding
+    /// the value that would otherwise be derived from the target features.
+    ///
+    /// Only relevant when targeting the `mips`/`mipsle` architectures.
+    pub fn gomips(&mut self, gomips: GoMips) -> &mut Self {
+        self.gomips = Some(gomips);
+        self
+    }
+
+    /// Instruct the builder to target the given `GOMIPS64` variant,
+    /// overriding the value that would otherwise be derived from the target
+    /// features.
+    ///
+    /// Only relevant when targeting the `mips64`/`mips64le` architectures.
+    pub fn gomips64(&mut self, gomips64: GoMips) -> &mut Self {
+        self.gomips64 = Some(gomips64);
+        self
+    }
+
+    /// Instruct the builder to target the given `GOPPC64` variant, overriding
+    /// the value that would otherwise be derived from the target features.
+    ///
+    /// Only relevant when targeting the `powerpc64`/`powerpc64le`
+    /// architectures.
+    pub fn goppc64(&mut self, goppc64: GoPpc64) -> &mut Self {
+        self.goppc64 = Some(goppc64);
+        self
+    }
+
     /// Instruct the builder to pass in the provided ldflags during compilation.
     pub fn ldflags<P: AsRef<OsStr>>(&mut self, ldflags: P) -> &mut Self {
         self.ldflags = Some(ldflags.as_ref().to_os_string());
@@ -113,6 +303,12 @@ impl Build {
         self
     }
 
+    /// Instruct the builder to pass the given build tags via `-tags`.
+    pub fn tags(&mut self, tags: &[&str]) -> &mut Self {
+        self.tags = tags.iter().map(|tag| tag.to_string()).collect();
+        self
+    }
+
     /// Instruct the builder to enable the `-trimpath` flag during compilation.
     pub fn trimpath(&mut self, trimpath: bool) -> &mut Self {
         self.trimpath = trimpath;
@@ -133,35 +329,82 @@ impl Build {
 
     /// Builds the Go package, generating the file `output`.
     pub fn try_build(&self, output: &str) -> Result<(), Error> {
-        let goos = goos_from_env()?;
         let goarch = goarch_from_env()?;
+        let is_wasm = goarch == "wasm";
+        let goos = goos_from_env(is_wasm)?;
+
+        // cgo, and so the `c-archive`/`c-shared` build modes, are
+        // unavailable on wasm; fall back to the default build mode, which
+        // produces a `.wasm` module when `-o` ends in `.wasm`.
+        let build_mode = if is_wasm {
+            if !matches!(self.build_mode, BuildMode::Default) && self.cargo_metadata {
+                println!(
+                    "cargo:warning=build_mode {} is not supported on wasm targets; using the default build mode instead",
+                    self.build_mode
+                );
+            }
+            BuildMode::Default
+        } else {
+            self.build_mode.clone()
+        };
 
-        let lib_name = self.format_lib_name(output);
+        let lib_name = self.format_lib_name(output, &build_mode, is_wasm);
         let out_dir = match &self.out_dir {
             Some(out_dir) => out_dir.clone(),
             None => get_env_var("OUT_DIR")?.into(),
         };
         let out_path = out_dir.join(lib_name);
 
-        let mut cmd = process::Command::new("go");
-        cmd.env("CGO_ENABLED", "1")
-            .env("GOOS", goos)
-            .env("GOARCH", goarch)
-            .env("CC", get_cc())
-            .env("CXX", get_cxx())
+        let cgo_enabled = if is_wasm { "0" } else { "1" };
+        let go_binary = self.resolve_go_binary();
+        if self.cargo_metadata {
+            let manifest_dir = get_env_var("CARGO_MANIFEST_DIR")?;
+            godeps::emit_rerun_if_changed(
+                &go_binary,
+                &[
+                    ("CGO_ENABLED", OsStr::new(cgo_enabled)),
+                    ("GOOS", OsStr::new(&goos)),
+                    ("GOARCH", OsStr::new(&goarch)),
+                ],
+                self.change_dir.as_deref(),
+                &self.packages,
+                Path::new(&manifest_dir),
+            )?;
+        }
+        let mut cmd = process::Command::new(&go_binary);
+        cmd.env("CGO_ENABLED", cgo_enabled)
+            .env("GOOS", &goos)
+            .env("GOARCH", &goarch)
             .arg("build");
+        if !is_wasm {
+            cmd.env("CC", get_cc()).env("CXX", get_cxx());
+        }
+        self.set_subarch_env(&mut cmd, &goarch)?;
+        for (key, val) in &self.envs {
+            cmd.env(key, val);
+        }
         if let Some(change_dir) = &self.change_dir {
             // This flag is required to be the first flag used in the command as
             // of Go v1.21: https://tip.golang.org/doc/go1.21#go-command
             cmd.args([&"-C".into(), change_dir]);
         }
+        if !self.tags.is_empty() {
+            cmd.args(["-tags", &self.tags.join(",")]);
+        }
         if let Some(ldflags) = &self.ldflags {
             cmd.args([&"-ldflags".into(), ldflags]);
         }
+        if let Some(gcflags) = &self.gcflags {
+            cmd.args([&"-gcflags".into(), gcflags]);
+        }
+        if let Some(asmflags) = &self.asmflags {
+            cmd.args([&"-asmflags".into(), asmflags]);
+        }
         if self.trimpath {
             cmd.arg("-trimpath");
         }
-        cmd.args(["-buildmode", &self.build_mode.to_string()]);
+        cmd.args(["-buildmode", &build_mode.to_string()]);
+        cmd.args(&self.flags);
         cmd.args(["-o".into(), out_path]);
         for package in &self.packages {
             cmd.arg(package);
@@ -172,26 +415,39 @@ impl Build {
             Err(err) => {
                 return Err(Error::new(
                     ErrorKind::ToolExecError,
-                    &format!("failed to execute go command: {}", err),
+                    &format!(
+                        "failed to execute go command ({}): {}",
+                        go_binary.display(),
+                        err
+                    ),
                 ));
             }
         };
 
         if self.cargo_metadata {
-            let link_kind = match self.build_mode {
-                BuildMode::CArchive => "static",
-                BuildMode::CShared => "dylib",
-            };
-            println!("cargo:rustc-link-lib={}={}", link_kind, output);
+            if let Some(link_kind) = build_mode.link_kind() {
+                println!("cargo:rustc-link-lib={}={}", link_kind, output);
+            }
             println!("cargo:rustc-link-search=native={}", out_dir.display());
         }
 
         if build_output.status.success() {
+            #[cfg(feature = "bindgen")]
+            if self.bindgen_enabled {
+                let config = bindings::BindgenConfig {
+                    allowlist: self.bindgen_allowlist.clone(),
+                    blocklist: self.bindgen_blocklist.clone(),
+                };
+                let header = out_dir.join(format!("lib{}.h", output));
+                let bindings_out = out_dir.join(format!("{}.rs", output));
+                bindings::generate(&header, &bindings_out, &config)?;
+            }
             return Ok(());
         }
 
         let mut message = format!(
-            "failed to build Go library ({}). Build output:",
+            "failed to build Go library using `{}` ({}). Build output:",
+            go_binary.display(),
             build_output.status
         );
 
@@ -212,25 +468,108 @@ impl Build {
         Err(Error::new(ErrorKind::ToolExecError, &message))
     }
 
-    fn format_lib_name(&self, output: &str) -> PathBuf {
+    /// Sets the Go environment variable that pins the microarchitecture/ABI
+    /// variant for `goarch`, if one applies, preferring an explicit override
+    /// over the value derived from the target triple and features.
+    fn set_subarch_env(&self, cmd: &mut process::Command, goarch: &str) -> Result<(), Error> {
+        match goarch {
+            "386" => {
+                let go386 = match &self.go386 {
+                    Some(go386) => go386.clone(),
+                    None => go386_from_env()?,
+                };
+                cmd.env("GO386", go386.to_string());
+            }
+            "amd64" => {
+                let goamd64 = match &self.goamd64 {
+                    Some(goamd64) => goamd64.clone(),
+                    None => goamd64_from_env()?,
+                };
+                cmd.env("GOAMD64", goamd64.to_string());
+            }
+            "arm" => {
+                let goarm = match &self.goarm {
+                    Some(goarm) => goarm.clone(),
+                    None => goarm_from_env()?,
+                };
+                cmd.env("GOARM", goarm.to_string());
+            }
+            "mips" | "mipsle" => {
+                let gomips = match &self.gomips {
+                    Some(gomips) => gomips.clone(),
+                    None => gomips_from_env()?,
+                };
+                cmd.env("GOMIPS", gomips.to_string());
+            }
+            "mips64" | "mips64le" => {
+                let gomips64 = match &self.gomips64 {
+                    Some(gomips64) => gomips64.clone(),
+                    None => gomips_from_env()?,
+                };
+                cmd.env("GOMIPS64", gomips64.to_string());
+            }
+            "ppc64" | "ppc64le" => {
+                let goppc64 = match &self.goppc64 {
+                    Some(goppc64) => goppc64.clone(),
+                    None => goppc64_from_env()?,
+                };
+                cmd.env("GOPPC64", goppc64.to_string());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Resolves the path to the Go toolchain binary to invoke, preferring an
+    /// explicit [`Build::go_binary`] override, then the `GOC` environment
+    /// variable, then a well-known install location, and finally `go` on
+    /// `PATH`.
+    fn resolve_go_binary(&self) -> PathBuf {
+        if let Some(go_binary) = &self.go_binary {
+            return go_binary.clone();
+        }
+        if let Ok(goc) = env::var("GOC") {
+            if !goc.is_empty() {
+                return goc.into();
+            }
+        }
+        let well_known = PathBuf::from("/usr/local/go/bin/go");
+        if well_known.is_file() {
+            return well_known;
+        }
+        PathBuf::from("go")
+    }
+
+    fn format_lib_name(&self, output: &str, build_mode: &BuildMode, is_wasm: bool) -> PathBuf {
+        if build_mode.is_executable() {
+            let mut exe = output.to_owned();
+            if is_wasm {
+                exe.push_str(".wasm");
+            } else if cfg!(windows) {
+                exe.push_str(".exe");
+            }
+            return exe.into();
+        }
+
         let mut lib = String::with_capacity(output.len() + 7);
         lib.push_str("lib");
         lib.push_str(output);
-        lib.push_str(match self.build_mode {
-            BuildMode::CArchive => {
+        lib.push_str(match build_mode {
+            BuildMode::CArchive | BuildMode::Archive => {
                 if cfg!(windows) {
                     ".lib"
                 } else {
                     ".a"
                 }
             }
-            BuildMode::CShared => {
+            BuildMode::CShared | BuildMode::Plugin | BuildMode::Shared => {
                 if cfg!(windows) {
                     ".dll"
                 } else {
                     ".so"
                 }
             }
+            BuildMode::Pie | BuildMode::Exe | BuildMode::Default => unreachable!(),
         });
         lib.into()
     }
@@ -253,6 +592,62 @@ pub enum BuildMode {
     /// be those functions exported using a cgo //export comment.
     /// Requires exactly one main package to be listed.
     CShared,
+    /// Build the listed main package, plus all packages it imports, into a
+    /// position-independent executable (PIE). Produces a plain executable
+    /// with no `lib` prefix; no `cargo:rustc-link-lib` directive is emitted
+    /// since there is no archive to link.
+    Pie,
+    /// Build the listed main package, plus all packages it imports, into an
+    /// executable, ignoring any `-buildmode` that would otherwise apply.
+    /// Produces a plain executable with no `lib` prefix; no
+    /// `cargo:rustc-link-lib` directive is emitted since there is no archive
+    /// to link.
+    Exe,
+    /// Build the listed main package, plus all packages it imports, into a
+    /// Go plugin, loadable at runtime via Go's `plugin` package.
+    Plugin,
+    /// Combine the listed packages, plus all packages they import, into a
+    /// single shared library that will be used when building with the
+    /// `-linkshared` flag.
+    Shared,
+    /// Listed main packages are built into executables and listed
+    /// non-main packages are built into `.a` files (the default
+    /// behavior of `go build`).
+    Default,
+    /// Build the listed non-main packages into `.a` files. Packages named
+    /// main are ignored.
+    Archive,
+}
+
+impl BuildMode {
+    /// Reports whether this build mode produces a plain executable rather
+    /// than a library.
+    fn is_executable(&self) -> bool {
+        matches!(self, Self::Pie | Self::Exe | Self::Default)
+    }
+
+    /// Returns the `cargo:rustc-link-lib` kind to emit for this build mode,
+    /// or `None` if the produced archive isn't a C-ABI-compatible library
+    /// rustc's linker can actually link against (executable-producing
+    /// modes, and `archive`, which is Go's internal package-archive format
+    /// with no cgo export header).
+    fn link_kind(&self) -> Option<&'static str> {
+        match self {
+            Self::CArchive => Some("static"),
+            Self::CShared => Some("dylib"),
+            // `plugin` artifacts are loaded dynamically via Go's `plugin`
+            // package (no cgo export header or C-callable symbol table),
+            // and `shared` artifacts are for other Go binaries built with
+            // `-linkshared`, not for arbitrary C/Rust consumers — neither
+            // is something rustc's linker can resolve symbols against.
+            Self::Plugin
+            | Self::Shared
+            | Self::Pie
+            | Self::Exe
+            | Self::Default
+            | Self::Archive => None,
+        }
+    }
 }
 
 impl std::fmt::Display for BuildMode {
@@ -260,6 +655,124 @@ impl std::fmt::Display for BuildMode {
         f.write_str(match self {
             Self::CArchive => "c-archive",
             Self::CShared => "c-shared",
+            Self::Pie => "pie",
+            Self::Exe => "exe",
+            Self::Plugin => "plugin",
+            Self::Shared => "shared",
+            Self::Default => "default",
+            Self::Archive => "archive",
+        })
+    }
+}
+
+/// `GO386` variant, controlling the floating-point ABI used on 32-bit x86.
+///
+/// For more information, see https://go.dev/wiki/MinimumRequirements#amd64
+#[derive(Clone, Debug)]
+pub enum Go386 {
+    /// Require SSE2 support, the default for modern 32-bit x86 CPUs.
+    Sse2,
+    /// Use software floating point, for CPUs without an SSE2 unit.
+    SoftFloat,
+}
+
+impl std::fmt::Display for Go386 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Sse2 => "sse2",
+            Self::SoftFloat => "softfloat",
+        })
+    }
+}
+
+/// `GOAMD64` variant, controlling the x86-64 microarchitecture level.
+///
+/// For more information, see https://go.dev/wiki/MinimumRequirements#amd64
+#[derive(Clone, Debug)]
+pub enum GoAmd64 {
+    /// Baseline x86-64, the default.
+    V1,
+    /// Requires, among others, SSE4.2 and POPCNT.
+    V2,
+    /// Requires, among others, AVX2 and FMA3.
+    V3,
+    /// Requires, among others, AVX512.
+    V4,
+}
+
+impl std::fmt::Display for GoAmd64 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::V1 => "v1",
+            Self::V2 => "v2",
+            Self::V3 => "v3",
+            Self::V4 => "v4",
+        })
+    }
+}
+
+/// `GOARM` variant, controlling the ARM floating-point ABI.
+///
+/// For more information, see https://go.dev/wiki/GoArm
+#[derive(Clone, Debug)]
+pub enum GoArm {
+    /// Software floating point.
+    V5,
+    /// Hardware floating point, with a software fallback.
+    V6,
+    /// Hardware floating point (VFPv3).
+    V7,
+}
+
+impl std::fmt::Display for GoArm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::V5 => "5",
+            Self::V6 => "6",
+            Self::V7 => "7",
+        })
+    }
+}
+
+/// `GOMIPS`/`GOMIPS64` variant, controlling the MIPS floating-point ABI.
+///
+/// For more information, see https://go.dev/doc/install/source#environment
+#[derive(Clone, Debug)]
+pub enum GoMips {
+    /// Hardware floating point, the default.
+    HardFloat,
+    /// Software floating point.
+    SoftFloat,
+}
+
+impl std::fmt::Display for GoMips {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::HardFloat => "hardfloat",
+            Self::SoftFloat => "softfloat",
+        })
+    }
+}
+
+/// `GOPPC64` variant, controlling the minimum POWER ISA level.
+///
+/// For more information, see https://go.dev/wiki/MinimumRequirements#ppc64
+#[derive(Clone, Debug)]
+pub enum GoPpc64 {
+    /// Baseline POWER8 ISA, the default.
+    Power8,
+    /// POWER9 ISA.
+    Power9,
+    /// POWER10 ISA.
+    Power10,
+}
+
+impl std::fmt::Display for GoPpc64 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Power8 => "power8",
+            Self::Power9 => "power9",
+            Self::Power10 => "power10",
         })
     }
 }
@@ -311,16 +824,42 @@ fn get_cxx() -> PathBuf {
 
 fn goarch_from_env() -> Result<String, Error> {
     let target_arch = get_env_var("CARGO_CFG_TARGET_ARCH")?;
+    // `CARGO_CFG_TARGET_ARCH` is identical for big- and little-endian
+    // MIPS/PPC64 pairs (e.g. `powerpc64-unknown-linux-gnu` and
+    // `powerpc64le-unknown-linux-gnu` both report `target_arch=powerpc64`);
+    // `CARGO_CFG_TARGET_ENDIAN` is what actually distinguishes them.
+    let is_little_endian = get_env_var("CARGO_CFG_TARGET_ENDIAN")? == "little";
 
     // From the following references:
     // https://doc.rust-lang.org/reference/conditional-compilation.html#target_arch
     // https://go.dev/doc/install/source#environment
     let goarch = match target_arch.as_str() {
-        "x86" => "386",
-        "x86_64" => "amd64",
-        "powerpc64" => "ppc64",
-        "aarch64" => "arm64",
-        "mips" | "mips64" | "arm" => &target_arch,
+        "x86" => "386".to_string(),
+        "x86_64" => "amd64".to_string(),
+        "powerpc64" => {
+            if is_little_endian {
+                "ppc64le".to_string()
+            } else {
+                "ppc64".to_string()
+            }
+        }
+        "aarch64" => "arm64".to_string(),
+        "wasm32" | "wasm64" => "wasm".to_string(),
+        "mips" => {
+            if is_little_endian {
+                "mipsle".to_string()
+            } else {
+                "mips".to_string()
+            }
+        }
+        "mips64" => {
+            if is_little_endian {
+                "mips64le".to_string()
+            } else {
+                "mips64".to_string()
+            }
+        }
+        "arm" => target_arch.clone(),
         _ => {
             return Err(Error::new(
                 ErrorKind::InvalidGOARCH,
@@ -328,12 +867,97 @@ fn goarch_from_env() -> Result<String, Error> {
             ))
         }
     };
-    Ok(goarch.to_string())
+    Ok(goarch)
+}
+
+fn target_features() -> Vec<String> {
+    get_env_var("CARGO_CFG_TARGET_FEATURE")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|feature| !feature.is_empty())
+        .map(str::to_owned)
+        .collect()
 }
 
-fn goos_from_env() -> Result<String, Error> {
+fn go386_from_env() -> Result<Go386, Error> {
+    let target = get_env_var("TARGET")?;
+    if target.starts_with("i586") {
+        Ok(Go386::SoftFloat)
+    } else {
+        Ok(Go386::Sse2)
+    }
+}
+
+fn goamd64_from_env() -> Result<GoAmd64, Error> {
+    let features = target_features();
+    if features.iter().any(|f| f == "avx512f") {
+        Ok(GoAmd64::V4)
+    } else if features.iter().any(|f| f == "avx2") {
+        Ok(GoAmd64::V3)
+    } else {
+        Ok(GoAmd64::V1)
+    }
+}
+
+fn goarm_from_env() -> Result<GoArm, Error> {
+    let target = get_env_var("TARGET")?;
+    // The ABI suffix (e.g. `gnueabihf`) distinguishes hardfloat targets like
+    // `arm-unknown-linux-gnueabihf` (ARMv6, Raspberry Pi 1/Zero) from
+    // softfloat ones like `arm-unknown-linux-gnueabi`.
+    let is_hardfloat = env::var("CARGO_CFG_TARGET_ABI")
+        .map(|abi| abi.ends_with("hf"))
+        .unwrap_or(false);
+    if !target.starts_with("armv7") && !target.starts_with("thumbv7") {
+        return Ok(if is_hardfloat { GoArm::V6 } else { GoArm::V5 });
+    }
+    let features = target_features();
+    if features.iter().any(|f| f == "vfp2" || f == "vfp3" || f == "vfp4" || f == "neon") {
+        Ok(GoArm::V7)
+    } else {
+        Ok(GoArm::V6)
+    }
+}
+
+fn gomips_from_env() -> Result<GoMips, Error> {
+    let features = target_features();
+    if features.iter().any(|f| f == "soft-float") {
+        Ok(GoMips::SoftFloat)
+    } else {
+        Ok(GoMips::HardFloat)
+    }
+}
+
+fn goppc64_from_env() -> Result<GoPpc64, Error> {
+    let features = target_features();
+    if features.iter().any(|f| f == "power10-vector") {
+        Ok(GoPpc64::Power10)
+    } else if features.iter().any(|f| f == "power9-vector") {
+        Ok(GoPpc64::Power9)
+    } else {
+        Ok(GoPpc64::Power8)
+    }
+}
+
+fn goos_from_env(is_wasm: bool) -> Result<String, Error> {
     let target_os = get_env_var("CARGO_CFG_TARGET_OS")?;
 
+    if is_wasm {
+        // Go only supports two OSes for GOARCH=wasm: `wasip1`, for the WASI
+        // target, and `js`, for browser/Node-hosted (`syscall/js`) targets.
+        // https://go.dev/wiki/WebAssembly
+        let goos = match target_os.as_str() {
+            "wasi" => "wasip1",
+            "unknown" | "emscripten" => "js",
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidGOOS,
+                    &format!("unexpected target os {} for wasm", target_os),
+                ))
+            }
+        };
+        return Ok(goos.to_string());
+    }
+
     // From the following references:
     // https://doc.rust-lang.org/reference/conditional-compilation.html#target_os
     // https://go.dev/doc/install/source#environment